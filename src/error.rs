@@ -0,0 +1,39 @@
+use std::ffi::NulError;
+use std::fmt;
+
+/// An error that occurred while loading a library or resolving a symbol from it.
+#[derive(Debug)]
+pub enum Error {
+	/// The library could not be loaded (not found, wrong architecture, missing
+	/// dependencies, ...). `message` is the OS-provided diagnostic.
+	LoadLibrary {
+		name: String,
+		message: String,
+	},
+	/// A symbol could not be found in an already-loaded library. `message` is
+	/// the OS-provided diagnostic.
+	SymbolNotFound {
+		symbol: String,
+		message: String,
+	},
+	/// A name contained an interior NUL byte and could not be converted to a `CStr`
+	InteriorNul(NulError),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::LoadLibrary { name, message } => write!(f, "failed to load library {name:?}: {message}"),
+			Self::SymbolNotFound { symbol, message } => write!(f, "symbol {symbol:?} not found: {message}"),
+			Self::InteriorNul(err) => write!(f, "name contains an interior NUL byte: {err}"),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+impl From<NulError> for Error {
+	fn from(err: NulError) -> Self {
+		Self::InteriorNul(err)
+	}
+}
@@ -0,0 +1,17 @@
+//! Low level library loading primitives.
+//!
+//! The actual implementation lives behind a platform-specific backend
+//! ([`windows`] on Windows, [`unix`] on POSIX systems), selected here with
+//! `cfg`. Both backends expose the same `Library`/`Symbol`/`RawSymbol`
+//! surface so the rest of the crate does not need to care which one it is
+//! built against.
+
+#[cfg(windows)]
+mod windows;
+#[cfg(windows)]
+pub use windows::{Library, Symbol, RawSymbol, NullableSymbol, Flags};
+
+#[cfg(unix)]
+mod unix;
+#[cfg(unix)]
+pub use unix::{Library, Symbol, RawSymbol, NullableSymbol, Flags};
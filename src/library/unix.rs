@@ -0,0 +1,425 @@
+use std::ffi::CStr;
+use std::marker::PhantomData;
+use std::sync::{Mutex, OnceLock};
+use libc::c_int;
+use crate::error::Error;
+
+/// Guards the clear-call-read `dlerror()` sequence used by [`Library::load_with_flags`] and
+/// [`Library::get_ptr`]. `dlerror` is only guaranteed thread-local since POSIX.1-2008, so
+/// without this, concurrent loads in this process could clobber each other's error state.
+fn dlerror_lock() -> &'static Mutex<()> {
+	static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+	LOCK.get_or_init(|| Mutex::new(()))
+}
+
+/// Reads and formats the `dlerror()` message currently pending, if any.
+///
+/// # SAFETY
+/// Must be called while holding the [`dlerror_lock`].
+unsafe fn dlerror_message() -> Option<String> {
+	// SAFETY: FFI
+	let error = unsafe { libc::dlerror() };
+	if error.is_null() {
+		None
+	} else {
+		// SAFETY: FFI
+		Some(unsafe { CStr::from_ptr(error) }.to_string_lossy().into_owned())
+	}
+}
+
+/// Flags controlling how a library is loaded with `dlopen`.
+///
+/// These correspond directly to the `RTLD_*` constants from `<dlfcn.h>`.
+/// Exactly one of [`Flags::LAZY`] / [`Flags::NOW`] should be set, optionally
+/// combined with [`Flags::GLOBAL`] or [`Flags::LOCAL`] via `|`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Flags(c_int);
+
+impl Flags {
+	/// Resolve symbols lazily, as they are used (`RTLD_LAZY`)
+	pub const LAZY: Self = Self(libc::RTLD_LAZY);
+	/// Resolve all symbols before `dlopen` returns (`RTLD_NOW`)
+	pub const NOW: Self = Self(libc::RTLD_NOW);
+	/// Make the library's symbols available to resolve relocations in other libraries (`RTLD_GLOBAL`)
+	pub const GLOBAL: Self = Self(libc::RTLD_GLOBAL);
+	/// Keep the library's symbols private to it (`RTLD_LOCAL`)
+	pub const LOCAL: Self = Self(libc::RTLD_LOCAL);
+	/// Never unload the library, even once its last reference is closed (`RTLD_NODELETE`)
+	pub const NODELETE: Self = Self(libc::RTLD_NODELETE);
+	/// Resolve the handle only if the library is already loaded; never load it (`RTLD_NOLOAD`)
+	pub const NOLOAD: Self = Self(libc::RTLD_NOLOAD);
+	/// Resolve the library's own dependencies against its own symbols before the global
+	/// scope (glibc/Linux extension) (`RTLD_DEEPBIND`)
+	#[cfg(target_os = "linux")]
+	pub const DEEPBIND: Self = Self(libc::RTLD_DEEPBIND);
+
+	const fn bits(self) -> c_int {
+		self.0
+	}
+}
+
+impl Default for Flags {
+	/// `RTLD_LAZY | RTLD_LOCAL`, the same default `dlopen` itself falls back to
+	fn default() -> Self {
+		Self::LAZY | Self::LOCAL
+	}
+}
+
+impl std::ops::BitOr for Flags {
+	type Output = Self;
+	fn bitor(self, rhs: Self) -> Self {
+		Self(self.0 | rhs.0)
+	}
+}
+
+/// Wrapper over a POSIX shared object
+///
+/// Automatically unloads the library when dropped
+pub struct Library {
+	handle: *mut libc::c_void,
+}
+
+unsafe impl Send for Library {}
+unsafe impl Sync for Library {}
+
+impl Library {
+	/// Load a library at the given path with the default flags (`RTLD_LAZY | RTLD_LOCAL`)
+	///
+	/// Returns an [Error] if the library does not exist or fails to load
+	pub fn load<A: AsRef<CStr>>(name: A) -> Result<Self, Error> {
+		// SAFETY: loading with the default flags runs the same initializers as any other
+		// load, which callers of the safe `load` constructor accept as inherent to loading
+		// a library
+		unsafe { Self::load_with_flags(name, Flags::default()) }
+	}
+
+	/// Load a library at the given path with the given `dlopen` flags
+	///
+	/// Returns an [Error] if the library does not exist or fails to load
+	///
+	/// # SAFETY
+	/// Loading a library runs its initializer routines, which is effectively an arbitrary
+	/// foreign function call. The caller must ensure that is sound.
+	pub unsafe fn load_with_flags<A: AsRef<CStr>>(name: A, flags: Flags) -> Result<Self, Error> {
+		let _guard = dlerror_lock().lock().unwrap();
+		// SAFETY: FFI, guarded by dlerror_lock
+		unsafe {
+			libc::dlerror();
+			let handle = libc::dlopen(name.as_ref().as_ptr(), flags.bits());
+			if !handle.is_null() {
+				Ok(Self { handle })
+			} else {
+				Err(Error::LoadLibrary {
+					name: name.as_ref().to_string_lossy().into_owned(),
+					message: dlerror_message().unwrap_or_else(|| "dlopen failed".to_string()),
+				})
+			}
+		}
+	}
+
+	/// Opens a handle to the already-loaded current process image (`dlopen(NULL, ...)`),
+	/// rather than loading a library from disk. This lets plugins resolve symbols that are
+	/// statically linked into their host executable, or injected via a preloaded library.
+	///
+	/// Unlike the Windows `GetModuleHandleW(NULL)` handle, the handle returned by
+	/// `dlopen(NULL, ...)` is reference-counted like any other `dlopen` handle, so it is
+	/// safe to `dlclose` on drop the same as [Library::load].
+	pub fn this() -> Result<Self, Error> {
+		let _guard = dlerror_lock().lock().unwrap();
+		// SAFETY: FFI, guarded by dlerror_lock
+		unsafe {
+			libc::dlerror();
+			let handle = libc::dlopen(std::ptr::null(), Flags::default().bits());
+			if !handle.is_null() {
+				Ok(Self { handle })
+			} else {
+				Err(Error::LoadLibrary {
+					name: "<current process>".to_string(),
+					message: dlerror_message().unwrap_or_else(|| "dlopen failed".to_string()),
+				})
+			}
+		}
+	}
+
+	/// Gets the address of a symbol from the input library.
+	/// Returns an [Error] if the symbol is not found. The type of the returned
+	/// symbol cannot be checked and must be verified by the caller
+	///
+	/// Unlike [RawSymbol], `Symbol` borrows from the [Library] which ensures it cannot be
+	/// used after the library is unloaded.
+	///
+	/// # SAFETY
+	/// The type of the symbol `T` MUST be verified.
+	///
+	/// If it is a function pointer, then then ABI, arguments and return type must be correct
+	///
+	/// If it is a pointer to a static member, then the type can be &T or &mut T if and only if the
+	/// reference aliasing rules are upheld, otherwise use *const T or *mut T
+	pub unsafe fn get<A: AsRef<CStr>, T>(&self, symbol_name: A) -> Result<Symbol<'_, T>, Error> {
+		unsafe {
+			self.get_raw(symbol_name).map(|raw| {
+				Symbol {
+					inner: raw,
+					_marker: PhantomData,
+				}
+			})
+		}
+	}
+
+	/// Gets the address of a symbol from the input library.
+	/// Returns an [Error] if the symbol is not found. The type of the returned
+	/// symbol cannot be checked and must be verified by the caller
+	///
+	/// The lifetime of the symbol is not checked. It is the responsibility of the
+	/// caller to ensure that the library is still loaded. Use [Symbol] for a version which
+	/// tracks lifetime
+	///
+	/// # SAFETY
+	/// The type of the symbol `T` MUST be verified.
+	///
+	/// If it is a function pointer, then then ABI, arguments and return type must be correct
+	///
+	/// If it is a pointer to a static member, then the type can be &T or &mut T if and only if the
+	/// reference aliasing rules are upheld, otherwise use *const T or *mut T
+	pub unsafe fn get_raw<A: AsRef<CStr>, T> (&self, symbol_name: A) -> Result<RawSymbol<T>, Error> {
+		let ptr = self.get_ptr(symbol_name)?;
+
+		Ok(RawSymbol {
+			ptr,
+			_marker: PhantomData,
+		})
+	}
+
+	/// Gets the raw address of a symbol from the input library.
+	///
+	/// `dlsym` returning null is ambiguous with a failed lookup, so per POSIX.1-2008 this
+	/// clears `dlerror()` first, performs the lookup, and only reports failure (an [Error])
+	/// if `dlerror()` is non-null afterwards. A symbol that is genuinely bound to a null
+	/// address is therefore returned as `Ok` of a null pointer rather than an error.
+	pub fn get_ptr<A: AsRef<CStr>>(&self, symbol_name: A) -> Result<*mut (), Error> {
+		let _guard = dlerror_lock().lock().unwrap();
+		// SAFETY: FFI, guarded by dlerror_lock. The clear-call-check sequence is the only
+		// way to distinguish a genuinely null symbol address from a failed lookup.
+		unsafe {
+			libc::dlerror();
+			let ptr = libc::dlsym(self.handle, symbol_name.as_ref().as_ptr());
+			match dlerror_message() {
+				None => Ok(ptr as *mut ()),
+				Some(message) => Err(Error::SymbolNotFound {
+					symbol: symbol_name.as_ref().to_string_lossy().into_owned(),
+					message,
+				}),
+			}
+		}
+	}
+
+	/// Gets a function pointer symbol from the library. Semantically identical to
+	/// [Library::get], spelled out for call sites where `T` is a `fn` type, mirroring
+	/// [Library::get_reference] for statically allocated objects.
+	///
+	/// # SAFETY
+	/// See [Library::get].
+	pub unsafe fn get_function<A: AsRef<CStr>, T>(&self, symbol_name: A) -> Result<Symbol<'_, T>, Error> {
+		unsafe { self.get(symbol_name) }
+	}
+
+	/// Gets a reference to a statically allocated `T` from the library.
+	/// Returns an [Error] if the symbol is not found, or if it resolves to a null address.
+	///
+	/// # SAFETY
+	/// The type `T` and the lifetime it is borrowed for must be verified by the caller,
+	/// same as [Library::get].
+	pub unsafe fn get_reference<A: AsRef<CStr>, T>(&self, symbol_name: A) -> Result<&T, Error> {
+		let symbol = symbol_name.as_ref();
+		let ptr = self.get_ptr(symbol)?;
+		if ptr.is_null() {
+			return Err(Error::SymbolNotFound {
+				symbol: symbol.to_string_lossy().into_owned(),
+				message: "symbol resolved to a null address".to_string(),
+			});
+		}
+		// SAFETY: caller-verified T and lifetime; ptr is non-null
+		unsafe { Ok(&*(ptr as *const T)) }
+	}
+
+	/// Gets a mutable reference to a statically allocated `T` from the library.
+	/// Returns an [Error] if the symbol is not found, or if it resolves to a null address.
+	///
+	/// # SAFETY
+	/// The type `T` and the lifetime it is borrowed for must be verified by the caller,
+	/// same as [Library::get]. The caller must also uphold the usual `&mut` aliasing
+	/// rules for the lifetime of the returned reference, same as [Library::get]'s `&mut T`
+	/// case.
+	pub unsafe fn get_reference_mut<A: AsRef<CStr>, T>(&self, symbol_name: A) -> Result<&mut T, Error> {
+		let symbol = symbol_name.as_ref();
+		let ptr = self.get_ptr(symbol)?;
+		if ptr.is_null() {
+			return Err(Error::SymbolNotFound {
+				symbol: symbol.to_string_lossy().into_owned(),
+				message: "symbol resolved to a null address".to_string(),
+			});
+		}
+		// SAFETY: caller-verified T and lifetime; ptr is non-null
+		unsafe { Ok(&mut *(ptr as *mut T)) }
+	}
+
+	/// Gets the address of a symbol from the input library as a possibly-null pointer,
+	/// for APIs where a null address is a valid sentinel value rather than a missing
+	/// symbol. Unlike [Library::get_reference], a null address is not treated as an
+	/// error; only a missing symbol is.
+	pub fn get_ptr_or_null<A: AsRef<CStr>, T>(&self, symbol_name: A) -> Result<NullableSymbol<'_, T>, Error> {
+		let ptr = self.get_ptr(symbol_name)? as *mut T;
+		Ok(NullableSymbol {
+			ptr,
+			_marker: PhantomData,
+		})
+	}
+
+	/// Identical to [Library::get_ptr_or_null], named separately for call sites that
+	/// intend to write through the pointer.
+	pub fn get_ptr_or_null_mut<A: AsRef<CStr>, T>(&self, symbol_name: A) -> Result<NullableSymbol<'_, T>, Error> {
+		self.get_ptr_or_null(symbol_name)
+	}
+}
+
+impl Drop for Library {
+	fn drop (&mut self) {
+		// SAFETY: FFI
+		unsafe{libc::dlclose(self.handle)};
+	}
+}
+
+/// Holds a pointer to some symbol retrieved from a library.
+/// It can be used with function pointers (fn(...) -> T) or static variables
+pub struct Symbol<'a, T> {
+	inner: RawSymbol<T>,
+	_marker: PhantomData<&'a T>,
+}
+
+unsafe impl<'a, T> Send for Symbol<'a, T> {}
+unsafe impl<'a, T> Sync for Symbol<'a, T> {}
+
+impl<'a, T> std::ops::Deref for Symbol<'a, T> {
+	type Target = T;
+	fn deref(&self) -> &Self::Target {
+		// SAFETY: 'a lifetime of self ensures library is not unloaded
+		unsafe {
+			self.inner.get()
+		}
+	}
+}
+
+impl<'a, T> std::ops::DerefMut for Symbol<'a, T> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		// SAFETY: 'a lifetime of self ensures library is not unloaded
+		unsafe {
+			self.inner.get_mut()
+		}
+	}
+}
+
+pub struct RawSymbol<T> {
+	ptr: *mut (),
+	_marker: PhantomData<T>,
+}
+
+/// Holds a pointer to a symbol that is explicitly allowed to resolve to a null address, e.g.
+/// an API where null is a valid sentinel value rather than "symbol missing". Unlike
+/// [Symbol]/[RawSymbol], dereferencing is left entirely to the caller: read [Self::as_ptr] /
+/// [Self::as_mut_ptr] and check for null before using it.
+pub struct NullableSymbol<'a, T> {
+	ptr: *mut T,
+	_marker: PhantomData<&'a T>,
+}
+
+unsafe impl<'a, T> Send for NullableSymbol<'a, T> {}
+unsafe impl<'a, T> Sync for NullableSymbol<'a, T> {}
+
+impl<'a, T> NullableSymbol<'a, T> {
+	/// Returns the raw pointer, which may be null
+	pub fn as_ptr(&self) -> *const T {
+		self.ptr
+	}
+
+	/// Returns the raw mutable pointer, which may be null
+	pub fn as_mut_ptr(&mut self) -> *mut T {
+		self.ptr
+	}
+}
+
+impl<T> RawSymbol<T> {
+	/// Turns a raw pointer into a raw symbol
+	///
+	/// # SAFETY
+	/// See [Library::get_raw]
+	pub unsafe fn from_ptr(ptr: *mut ()) -> Self {
+		Self {
+			ptr,
+			_marker: PhantomData,
+		}
+	}
+
+	/// Gets a reference to the pointer returned from get_raw
+	///
+	/// # Safety:
+	/// Library must still be loaded
+	pub unsafe fn get(&self) -> &T {
+		unsafe {
+			std::mem::transmute(&self.ptr)
+		}
+	}
+
+	/// Gets a mutable reference to the pointer returned from get_raw
+	///
+	/// # Safety:
+	/// Library must still be loaded
+	pub unsafe fn get_mut(&mut self) -> &mut T {
+		unsafe {
+			std::mem::transmute(&mut self.ptr)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::ffi::CString;
+	use std::path::PathBuf;
+
+	/// Compiles a tiny shared object exporting the given `--defsym` bindings
+	/// (e.g. `"some_symbol=0"`), so tests can exercise `get_ptr` against a symbol
+	/// whose address is known ahead of time, including a symbol that is genuinely
+	/// bound to address 0.
+	fn build_fixture(name: &str, defsyms: &[&str]) -> PathBuf {
+		let path = std::env::temp_dir().join(format!("my_libloading_test_{name}.so"));
+		let mut cmd = std::process::Command::new("cc");
+		cmd.args(["-shared", "-fPIC", "-o"]).arg(&path).args(["-x", "c", "/dev/null"]);
+		for defsym in defsyms {
+			cmd.arg(format!("-Wl,--defsym={defsym}"));
+		}
+		let status = cmd.status().expect("failed to invoke cc to build test fixture");
+		assert!(status.success(), "cc failed to build test fixture");
+		path
+	}
+
+	#[test]
+	fn get_ptr_distinguishes_null_symbol_from_missing_symbol() {
+		let path = build_fixture(
+			"get_ptr_null_vs_missing",
+			&["test_null_symbol=0", "test_present_symbol=0x1234"],
+		);
+		let lib = Library::load(CString::new(path.to_str().unwrap()).unwrap()).unwrap();
+
+		// A symbol that genuinely resolves to address 0 is `Ok`, not an error.
+		let null_ptr = lib.get_ptr(c"test_null_symbol").unwrap();
+		assert!(null_ptr.is_null());
+
+		// A symbol that resolves to a real address is `Ok` of that address.
+		let present_ptr = lib.get_ptr(c"test_present_symbol").unwrap();
+		assert!(!present_ptr.is_null());
+
+		// A symbol that isn't in the library at all is an `Error`, not a null pointer.
+		let err = lib.get_ptr(c"symbol_that_does_not_exist_anywhere").unwrap_err();
+		assert!(matches!(err, Error::SymbolNotFound { .. }));
+	}
+}
@@ -0,0 +1,395 @@
+use std::ffi::CStr;
+use std::marker::PhantomData;
+use windows_sys::Win32::System::LibraryLoader::{
+	LoadLibraryA, LoadLibraryExW, GetModuleHandleW, GetProcAddress,
+	LOAD_LIBRARY_SEARCH_SYSTEM32, LOAD_LIBRARY_SEARCH_DLL_LOAD_DIR,
+	LOAD_LIBRARY_SEARCH_APPLICATION_DIR, LOAD_LIBRARY_SEARCH_USER_DIRS,
+	LOAD_LIBRARY_SEARCH_DEFAULT_DIRS, LOAD_WITH_ALTERED_SEARCH_PATH,
+};
+use windows_sys::Win32::Foundation::{FreeLibrary, GetLastError, HMODULE};
+use windows_sys::Win32::System::Diagnostics::Debug::{FormatMessageW, FORMAT_MESSAGE_FROM_SYSTEM, FORMAT_MESSAGE_IGNORE_INSERTS};
+use crate::error::Error;
+
+/// Flags controlling the DLL search path used by `LoadLibraryExW`.
+///
+/// These correspond directly to the `LOAD_LIBRARY_SEARCH_*` / `LOAD_WITH_ALTERED_SEARCH_PATH`
+/// constants. Restricting the search path with these avoids the "found the wrong copy of the
+/// DLL" / DLL-hijacking bugs that `LoadLibraryA`'s default search order is prone to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Flags(u32);
+
+impl Flags {
+	/// Search only `%SystemRoot%\System32` (`LOAD_LIBRARY_SEARCH_SYSTEM32`)
+	pub const SEARCH_SYSTEM32: Self = Self(LOAD_LIBRARY_SEARCH_SYSTEM32);
+	/// Search the directory the DLL being loaded is in (`LOAD_LIBRARY_SEARCH_DLL_LOAD_DIR`)
+	pub const SEARCH_DLL_LOAD_DIR: Self = Self(LOAD_LIBRARY_SEARCH_DLL_LOAD_DIR);
+	/// Search the application directory (`LOAD_LIBRARY_SEARCH_APPLICATION_DIR`)
+	pub const SEARCH_APPLICATION_DIR: Self = Self(LOAD_LIBRARY_SEARCH_APPLICATION_DIR);
+	/// Search the directories added with `AddDllDirectory` or `SetDllDirectory` (`LOAD_LIBRARY_SEARCH_USER_DIRS`)
+	pub const SEARCH_USER_DIRS: Self = Self(LOAD_LIBRARY_SEARCH_USER_DIRS);
+	/// The default search order used when none of the other `SEARCH_*` flags are set (`LOAD_LIBRARY_SEARCH_DEFAULT_DIRS`)
+	pub const SEARCH_DEFAULT_DIRS: Self = Self(LOAD_LIBRARY_SEARCH_DEFAULT_DIRS);
+	/// Use the directory of the given path as the first entry of the search path for the
+	/// DLL's own dependencies (`LOAD_WITH_ALTERED_SEARCH_PATH`); cannot be combined with the
+	/// `SEARCH_*` flags
+	pub const ALTERED_SEARCH_PATH: Self = Self(LOAD_WITH_ALTERED_SEARCH_PATH);
+
+	const fn bits(self) -> u32 {
+		self.0
+	}
+}
+
+impl Default for Flags {
+	/// `LOAD_LIBRARY_SEARCH_DEFAULT_DIRS`, restricting the search path instead of relying on
+	/// `LoadLibraryA`'s hijack-prone default order
+	fn default() -> Self {
+		Self::SEARCH_DEFAULT_DIRS
+	}
+}
+
+impl std::ops::BitOr for Flags {
+	type Output = Self;
+	fn bitor(self, rhs: Self) -> Self {
+		Self(self.0 | rhs.0)
+	}
+}
+
+/// Formats `GetLastError()` into a human readable message, the way `FormatMessageW` does
+/// for system error codes.
+fn last_error_message() -> String {
+	// SAFETY: FFI
+	unsafe {
+		let code = GetLastError();
+		let mut buf = [0u16; 512];
+		let len = FormatMessageW(
+			FORMAT_MESSAGE_FROM_SYSTEM | FORMAT_MESSAGE_IGNORE_INSERTS,
+			std::ptr::null(),
+			code,
+			0,
+			buf.as_mut_ptr(),
+			buf.len() as u32,
+			std::ptr::null(),
+		);
+		if len == 0 {
+			format!("OS error {code}")
+		} else {
+			String::from_utf16_lossy(&buf[..len as usize]).trim_end().to_string()
+		}
+	}
+}
+
+/// Wrapper over windows dll
+///
+/// Automatically unloads the library when dropped, unless it was opened with [Library::this]
+pub struct Library {
+	module: HMODULE,
+	/// `false` for [Library::this], whose handle is not reference-counted the way
+	/// `LoadLibrary`'s is and so must not be passed to `FreeLibrary`
+	owned: bool,
+}
+
+unsafe impl Send for Library {}
+unsafe impl Sync for Library {}
+
+impl Library {
+	/// Load a library at the given path
+	///
+	/// Returns an [Error] if the library does not exist or fails to load
+	pub fn load<A: AsRef<CStr>>(name: A) -> Result<Self, Error> {
+		// SAFETY: FFI
+		let module = unsafe{LoadLibraryA(name.as_ref().as_ptr().cast())};
+		if module != 0 {
+			Ok(Self {
+				module,
+				owned: true,
+			})
+		} else {
+			Err(Error::LoadLibrary {
+				name: name.as_ref().to_string_lossy().into_owned(),
+				message: last_error_message(),
+			})
+		}
+	}
+
+	/// Load a library at the given path through `LoadLibraryExW` with the given search-path
+	/// [Flags], instead of `LoadLibraryA`'s default (hijack-prone) search order.
+	///
+	/// Returns an [Error] if the library does not exist or fails to load
+	///
+	/// # SAFETY
+	/// Loading a library runs its initializer routines, which is effectively an arbitrary
+	/// foreign function call. The caller must ensure that is sound.
+	pub unsafe fn load_with_flags<A: AsRef<CStr>>(name: A, flags: Flags) -> Result<Self, Error> {
+		let wide_name: Vec<u16> = name.as_ref().to_string_lossy()
+			.encode_utf16()
+			.chain(std::iter::once(0))
+			.collect();
+
+		// SAFETY: FFI
+		let module = unsafe{LoadLibraryExW(wide_name.as_ptr(), 0, flags.bits())};
+		if module != 0 {
+			Ok(Self {
+				module,
+				owned: true,
+			})
+		} else {
+			Err(Error::LoadLibrary {
+				name: name.as_ref().to_string_lossy().into_owned(),
+				message: last_error_message(),
+			})
+		}
+	}
+
+	/// Opens a handle to the already-loaded current process image, rather than loading a
+	/// library from disk. This lets plugins resolve symbols that are statically linked into
+	/// their host executable, or injected via a preloaded library.
+	///
+	/// Unlike [Library::load], dropping the returned `Library` does not call `FreeLibrary`:
+	/// `GetModuleHandleW(NULL)`'s handle is not reference-counted the way `LoadLibrary`'s is.
+	pub fn this() -> Result<Self, Error> {
+		// SAFETY: FFI
+		let module = unsafe{GetModuleHandleW(std::ptr::null())};
+		if module != 0 {
+			Ok(Self {
+				module,
+				owned: false,
+			})
+		} else {
+			Err(Error::LoadLibrary {
+				name: "<current process>".to_string(),
+				message: last_error_message(),
+			})
+		}
+	}
+
+	/// Gets the address of a symbol from the input library.
+	/// Returns an [Error] if the symbol is not found. The type of the returned
+	/// symbol cannot be checked and must be verified by the caller
+	///
+	/// Unlike [RawSymbol], `Symbol` borrows from the [Library] which ensures it cannot be
+	/// used after the library is unloaded.
+	///
+	/// # SAFETY
+	/// The type of the symbol `T` MUST be verified.
+	///
+	/// If it is a function pointer, then then ABI, arguments and return type must be correct
+	///
+	/// If it is a pointer to a static member, then the type can be &T or &mut T if and only if the
+	/// reference aliasing rules are upheld, otherwise use *const T or *mut T
+	pub unsafe fn get<A: AsRef<CStr>, T>(&self, symbol_name: A) -> Result<Symbol<'_, T>, Error> {
+		unsafe {
+			self.get_raw(symbol_name).map(|raw| {
+				Symbol {
+					inner: raw,
+					_marker: PhantomData,
+				}
+			})
+		}
+	}
+
+	/// Gets the address of a symbol from the input library.
+	/// Returns an [Error] if the symbol is not found. The type of the returned
+	/// symbol cannot be checked and must be verified by the caller
+	///
+	/// The lifetime of the symbol is not checked. It is the responsibility of the
+	/// caller to ensure that the library is still loaded. Use [Symbol] for a version which
+	/// tracks lifetime
+	///
+	/// # SAFETY
+	/// The type of the symbol `T` MUST be verified.
+	///
+	/// If it is a function pointer, then then ABI, arguments and return type must be correct
+	///
+	/// If it is a pointer to a static member, then the type can be &T or &mut T if and only if the
+	/// reference aliasing rules are upheld, otherwise use *const T or *mut T
+	pub unsafe fn get_raw<A: AsRef<CStr>, T> (&self, symbol_name: A) -> Result<RawSymbol<T>, Error> {
+		// SAFETY: FFI
+		let ptr = self.get_ptr(symbol_name)?;
+
+		Ok(RawSymbol {
+			ptr,
+			_marker: PhantomData,
+		})
+	}
+
+	/// Gets the raw address of a symbol from the input library, returning an [Error]
+	/// carrying the OS diagnostic if the symbol is not found.
+	pub fn get_ptr<A: AsRef<CStr>>(&self, symbol_name: A) -> Result<*mut (), Error> {
+		// SAFETY: FFI
+		match unsafe{GetProcAddress(self.module, symbol_name.as_ref().as_ptr().cast())} {
+			Some(ptr) => Ok(ptr as *mut ()),
+			None => Err(Error::SymbolNotFound {
+				symbol: symbol_name.as_ref().to_string_lossy().into_owned(),
+				message: last_error_message(),
+			}),
+		}
+	}
+
+	/// Gets a function pointer symbol from the library. Semantically identical to
+	/// [Library::get], spelled out for call sites where `T` is a `fn` type, mirroring
+	/// [Library::get_reference] for statically allocated objects.
+	///
+	/// # SAFETY
+	/// See [Library::get].
+	pub unsafe fn get_function<A: AsRef<CStr>, T>(&self, symbol_name: A) -> Result<Symbol<'_, T>, Error> {
+		unsafe { self.get(symbol_name) }
+	}
+
+	/// Gets a reference to a statically allocated `T` from the library.
+	/// Returns an [Error] if the symbol is not found, or if it resolves to a null address.
+	///
+	/// # SAFETY
+	/// The type `T` and the lifetime it is borrowed for must be verified by the caller,
+	/// same as [Library::get].
+	pub unsafe fn get_reference<A: AsRef<CStr>, T>(&self, symbol_name: A) -> Result<&T, Error> {
+		let symbol = symbol_name.as_ref();
+		let ptr = self.get_ptr(symbol)?;
+		if ptr.is_null() {
+			return Err(Error::SymbolNotFound {
+				symbol: symbol.to_string_lossy().into_owned(),
+				message: "symbol resolved to a null address".to_string(),
+			});
+		}
+		// SAFETY: caller-verified T and lifetime; ptr is non-null
+		unsafe { Ok(&*(ptr as *const T)) }
+	}
+
+	/// Gets a mutable reference to a statically allocated `T` from the library.
+	/// Returns an [Error] if the symbol is not found, or if it resolves to a null address.
+	///
+	/// # SAFETY
+	/// The type `T` and the lifetime it is borrowed for must be verified by the caller,
+	/// same as [Library::get]. The caller must also uphold the usual `&mut` aliasing
+	/// rules for the lifetime of the returned reference, same as [Library::get]'s `&mut T`
+	/// case.
+	pub unsafe fn get_reference_mut<A: AsRef<CStr>, T>(&self, symbol_name: A) -> Result<&mut T, Error> {
+		let symbol = symbol_name.as_ref();
+		let ptr = self.get_ptr(symbol)?;
+		if ptr.is_null() {
+			return Err(Error::SymbolNotFound {
+				symbol: symbol.to_string_lossy().into_owned(),
+				message: "symbol resolved to a null address".to_string(),
+			});
+		}
+		// SAFETY: caller-verified T and lifetime; ptr is non-null
+		unsafe { Ok(&mut *(ptr as *mut T)) }
+	}
+
+	/// Gets the address of a symbol from the input library as a possibly-null pointer,
+	/// for APIs where a null address is a valid sentinel value rather than a missing
+	/// symbol. Unlike [Library::get_reference], a null address is not treated as an
+	/// error; only a missing symbol is.
+	pub fn get_ptr_or_null<A: AsRef<CStr>, T>(&self, symbol_name: A) -> Result<NullableSymbol<'_, T>, Error> {
+		let ptr = self.get_ptr(symbol_name)? as *mut T;
+		Ok(NullableSymbol {
+			ptr,
+			_marker: PhantomData,
+		})
+	}
+
+	/// Identical to [Library::get_ptr_or_null], named separately for call sites that
+	/// intend to write through the pointer.
+	pub fn get_ptr_or_null_mut<A: AsRef<CStr>, T>(&self, symbol_name: A) -> Result<NullableSymbol<'_, T>, Error> {
+		self.get_ptr_or_null(symbol_name)
+	}
+}
+
+impl Drop for Library {
+	fn drop (&mut self) {
+		if self.owned {
+			// SAFETY: FFI
+			unsafe{FreeLibrary(self.module)};
+		}
+	}
+}
+
+/// Holds a pointer to some symbol retrieved from a library.
+/// It can be used with function pointers (fn(...) -> T) or static variables
+pub struct Symbol<'a, T> {
+	inner: RawSymbol<T>,
+	_marker: PhantomData<&'a T>,
+}
+
+unsafe impl<'a, T> Send for Symbol<'a, T> {}
+unsafe impl<'a, T> Sync for Symbol<'a, T> {}
+
+impl<'a, T> std::ops::Deref for Symbol<'a, T> {
+	type Target = T;
+	fn deref(&self) -> &Self::Target {
+		// SAFETY: 'a lifetime of self ensures library is not unloaded
+		unsafe {
+			self.inner.get()
+		}
+	}
+}
+
+impl<'a, T> std::ops::DerefMut for Symbol<'a, T> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		// SAFETY: 'a lifetime of self ensures library is not unloaded
+		unsafe {
+			self.inner.get_mut()
+		}
+	}
+}
+
+pub struct RawSymbol<T> {
+	ptr: *mut (),
+	_marker: PhantomData<T>,
+}
+
+/// Holds a pointer to a symbol that is explicitly allowed to resolve to a null address, e.g.
+/// an API where null is a valid sentinel value rather than "symbol missing". Unlike
+/// [Symbol]/[RawSymbol], dereferencing is left entirely to the caller: read [Self::as_ptr] /
+/// [Self::as_mut_ptr] and check for null before using it.
+pub struct NullableSymbol<'a, T> {
+	ptr: *mut T,
+	_marker: PhantomData<&'a T>,
+}
+
+unsafe impl<'a, T> Send for NullableSymbol<'a, T> {}
+unsafe impl<'a, T> Sync for NullableSymbol<'a, T> {}
+
+impl<'a, T> NullableSymbol<'a, T> {
+	/// Returns the raw pointer, which may be null
+	pub fn as_ptr(&self) -> *const T {
+		self.ptr
+	}
+
+	/// Returns the raw mutable pointer, which may be null
+	pub fn as_mut_ptr(&mut self) -> *mut T {
+		self.ptr
+	}
+}
+
+impl<T> RawSymbol<T> {
+	/// Turns a raw pointer into a raw symbol
+	///
+	/// # SAFETY
+	/// See [Library::get_raw]
+	pub unsafe fn from_ptr(ptr: *mut ()) -> Self {
+		Self {
+			ptr,
+			_marker: PhantomData,
+		}
+	}
+	
+	/// Gets a reference to the pointer returned from get_raw
+	/// 
+	/// # Safety: 
+	/// Library must still be loaded
+	pub unsafe fn get(&self) -> &T {
+		unsafe {
+			std::mem::transmute(&self.ptr)
+		}
+	}
+	
+	/// Gets a mutable reference to the pointer returned from get_raw
+	/// 
+	/// # Safety: 
+	/// Library must still be loaded
+	pub unsafe fn get_mut(&mut self) -> &mut T {
+		unsafe {
+			std::mem::transmute(&mut self.ptr)
+		}
+	}
+}
@@ -1,13 +1,103 @@
 use crate::library::*;
+use crate::error::Error;
 use std::ffi::{CStr, CString};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::marker::PhantomData;
+use std::time::SystemTime;
 use arc_atomic::AtomicArc;
 
 pub struct ReloadableLibrary {
 	name: &'static CStr,
+	flags: Option<Flags>,
 	inner: AtomicArc<Inner>,
 	symbols: Box<[CString]>,
+	snapshot: Mutex<Option<FileSnapshot>>,
+}
+
+/// Loads `name` directly, through [Library::load] if `flags` is `None` or with the
+/// caller-supplied flags (e.g. a restricted DLL search path / `dlopen` mode) otherwise.
+///
+/// Used for the initial load in [ReloadableLibrary::new], where there is no stale handle
+/// of `name` still open and so no reason to pay for [load_fresh]'s temp-copy dance. Loading
+/// `name` directly also means sibling files the library depends on (e.g. via `$ORIGIN`
+/// rpath on Unix or `LOAD_LIBRARY_SEARCH_DLL_LOAD_DIR` on Windows) resolve the way they
+/// would for any other load of `name`.
+fn load(name: &CStr, flags: Option<Flags>) -> Result<Library, Error> {
+	match flags {
+		// SAFETY: loading with caller-supplied flags runs the same initializers as any
+		// other load, which callers of `ReloadableLibrary` accept as inherent to loading
+		// a library
+		Some(flags) => unsafe { Library::load_with_flags(name, flags) },
+		None => Library::load(name),
+	}
+}
+
+/// Loads `name` via a freshly-named temporary copy of the file, falling back to loading
+/// `name` directly if the copy can't be made.
+///
+/// [ReloadableLibrary::reload]/[ReloadableLibrary::try_reload] call this while the *previous* load of `name` is still
+/// held open, so that a failed reload leaves it undisturbed. But `dlopen`/`LoadLibrary` both
+/// identify an already-loaded module by the path string passed in, not by its current
+/// contents, so loading `name` a second time while the first handle is still open would just
+/// hand back the stale, already-resident copy instead of noticing the file changed. Loading a
+/// freshly-named temporary copy of the file sidesteps that; if the copy can't be made (e.g.
+/// `name` isn't valid UTF-8, or the filesystem doesn't allow it), this falls back to loading
+/// `name` directly, in which case a reload may not observe a change.
+///
+/// Note this breaks resolution of any sibling files the library depends on via a
+/// load-directory-relative search path, since those siblings won't exist next to the temp
+/// copy; callers whose libraries have such dependencies should expect `reload`/`try_reload`
+/// to fail for that reason.
+fn load_fresh(name: &CStr, flags: Option<Flags>) -> Result<Library, Error> {
+	let copy = copy_to_temp(name);
+	let load_name = copy.as_deref().unwrap_or(name);
+
+	let result = load(load_name, flags);
+
+	if let Some(copy) = &copy {
+		// Best-effort cleanup. On Unix this is safe even though the library is still
+		// mapped: the backing inode stays alive until the last mapping of it is gone.
+		// Platforms that lock open files (notably Windows) may fail to remove it here,
+		// in which case the temporary copy is simply leaked.
+		let _ = std::fs::remove_file(copy.to_string_lossy().as_ref());
+	}
+
+	result
+}
+
+/// Copies the file at `name` into the system temp directory under a fresh, unique name
+/// that keeps the file's extension (`LoadLibrary` appends `.dll` to a path without one).
+/// Returns `None` if `name` isn't valid UTF-8 or the copy fails.
+fn copy_to_temp(name: &CStr) -> Option<CString> {
+	static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+	let source = name.to_str().ok()?;
+	let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+	let mut dest = std::env::temp_dir().join(format!("my_libloading-{}-{id}", std::process::id()));
+	if let Some(extension) = std::path::Path::new(source).extension() {
+		dest.set_extension(extension);
+	}
+	std::fs::copy(source, &dest).ok()?;
+	CString::new(dest.to_str()?).ok()
+}
+
+/// A cheap fingerprint of a library file on disk, used by [ReloadableLibrary::try_reload] to
+/// tell whether the file has actually changed since it was last loaded.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct FileSnapshot {
+	modified: SystemTime,
+	len: u64,
+}
+
+/// Takes a [FileSnapshot] of `name`. Returns `None` if `name` isn't a valid UTF-8 path or its
+/// metadata can't be read, in which case the caller should fall back to always reloading.
+fn snapshot(name: &CStr) -> Option<FileSnapshot> {
+	let metadata = std::fs::metadata(name.to_str().ok()?).ok()?;
+	Some(FileSnapshot {
+		modified: metadata.modified().ok()?,
+		len: metadata.len(),
+	})
 }
 
 struct Inner {
@@ -16,42 +106,44 @@ struct Inner {
 }
 
 impl Inner {
-	pub fn new(_lib: Library, symbols: &[CString]) -> Self {
-		let pointers = symbols.into_iter().map(|symbol| _lib.get_ptr(&symbol)
-				.unwrap_or_else(|| panic!("Could not find symbol: {:?}", symbol))
-			)
-			.collect::<Box<[_]>>();
-		Self {
+	pub fn new(_lib: Library, symbols: &[CString]) -> Result<Self, Error> {
+		let pointers = symbols.into_iter().map(|symbol| _lib.get_ptr(&symbol))
+			.collect::<Result<Box<[_]>, _>>()?;
+		Ok(Self {
 			_lib,
 			pointers,
-		}
+		})
 	}
 }
 
 impl ReloadableLibrary {
-	/// Create a new ReloadableLibrary with the given name and load the given symbols from it
-	/// Panics if the library is not found or if any of the symbols are not found.
+	/// Create a new ReloadableLibrary with the given name and load the given symbols from it.
+	/// `flags`, if given, control the search path / load mode used both now and on every
+	/// [Self::reload]; `None` loads through [Library::load].
+	///
+	/// Returns an [Error] if the library is not found or if any of the symbols are not found.
 	///
 	/// symbols are not deduplicated so each instance of a duplicated symbol must be loaded,
-	/// but only the first instance can be obtained through [Self::get_symbol], so consider 
+	/// but only the first instance can be obtained through [Self::get_symbol], so consider
 	/// depduplicating symbols before passing them in
-	pub fn new<const N: usize>(name: &'static CStr, symbols: [CString;N]) -> Self {
+	pub fn new<const N: usize>(name: &'static CStr, symbols: [CString;N], flags: Option<Flags>) -> Result<Self, Error> {
 		// Load library
-		let lib = Library::load(name)
-			.unwrap_or_else(|| panic!("Could not load library {:?}", name));
+		let lib = load(name, flags)?;
 
 		// turn library into Inner and put it in an atomic arc
 		let inner = AtomicArc::new(
 			Arc::new(
-				Inner::new(lib, &symbols)
+				Inner::new(lib, &symbols)?
 			)
 		);
 
-		Self {
+		Ok(Self {
 			name,
+			flags,
 			symbols: (&symbols as &[CString]).into(),
 			inner,
-		}
+			snapshot: Mutex::new(snapshot(name)),
+		})
 	}
 
 	pub unsafe fn get_symbol<T>(&self, symbol: &CStr) -> Option<ReloadableSymbol<T>> {
@@ -72,12 +164,49 @@ impl ReloadableLibrary {
 		})
 	}
 
-	pub fn reload(&self) {
-		// Load new library
-		let lib = Library::load(self.name)
-			.unwrap_or_else(|| panic!("Could not reload library {:?}", self.name));
+	/// Unconditionally reloads the library from disk and re-resolves all symbols from it,
+	/// regardless of whether the backing file has changed. See [Self::try_reload] for a
+	/// version that skips the reload when the file is unchanged.
+	///
+	/// The new library and all of its symbols are fully resolved before the currently
+	/// published one is replaced, so a failed reload leaves the old library in place and
+	/// any [LoadedSymbol]s obtained from it remain valid.
+	///
+	/// Returns an [Error] if the library fails to load or any symbol cannot be resolved.
+	pub fn reload(&self) -> Result<(), Error> {
+		let inner = self.load_inner()?;
+		self.inner.store(Arc::new(inner));
+		*self.snapshot.lock().unwrap() = snapshot(self.name);
+		Ok(())
+	}
+
+	/// Reloads the library from disk, but only if its backing file has changed since the
+	/// last load/reload (compared by modification time and size). Returns `Ok(false)`
+	/// without touching the published library if nothing has changed, so callers can poll
+	/// this cheaply, e.g. once per frame.
+	///
+	/// As with [Self::reload], the new library and all of its symbols are fully resolved
+	/// before the currently published one is replaced, so a failed reload leaves the old
+	/// library in place and any [LoadedSymbol]s obtained from it remain valid.
+	///
+	/// Returns an [Error] if the library fails to load or any symbol cannot be resolved.
+	pub fn try_reload(&self) -> Result<bool, Error> {
+		let current = snapshot(self.name);
+		if current.is_some() && current == *self.snapshot.lock().unwrap() {
+			return Ok(false);
+		}
+
+		let inner = self.load_inner()?;
+		self.inner.store(Arc::new(inner));
+		*self.snapshot.lock().unwrap() = current;
+		Ok(true)
+	}
 
-		self.inner.store(Arc::new(Inner::new(lib, &*self.symbols)));
+	/// Loads a fresh copy of the library and resolves all of its symbols into a new [Inner],
+	/// without touching the currently published one.
+	fn load_inner(&self) -> Result<Inner, Error> {
+		let lib = load_fresh(self.name, self.flags)?;
+		Inner::new(lib, &self.symbols)
 	}
 }
 
@@ -141,3 +270,57 @@ impl<T> std::ops::DerefMut for LoadedSymbol<T> {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// (Re)compiles a tiny shared object exporting the given `--defsym` bindings
+	/// (e.g. `"symbol=0x1234"`) and atomically renames it into place at `path`.
+	/// Used to control a fixture's resolved symbol addresses and on-disk size
+	/// independently of its mtime, so a reload can be triggered deterministically.
+	fn write_fixture(path: &std::path::Path, defsyms: &[&str]) {
+		let scratch = path.with_extension(format!("{}.tmp", std::process::id()));
+		let mut cmd = std::process::Command::new("cc");
+		cmd.args(["-shared", "-fPIC", "-o"]).arg(&scratch).args(["-x", "c", "/dev/null"]);
+		for defsym in defsyms {
+			cmd.arg(format!("-Wl,--defsym={defsym}"));
+		}
+		let status = cmd.status().expect("failed to invoke cc to build test fixture");
+		assert!(status.success(), "cc failed to build test fixture");
+		std::fs::rename(&scratch, path).expect("failed to rename test fixture into place");
+	}
+
+	#[test]
+	fn try_reload_skips_unchanged_file_and_rolls_back_on_failure() {
+		let path = std::env::temp_dir().join("my_libloading_test_try_reload.so");
+		write_fixture(&path, &["reload_symbol=0x1111"]);
+
+		let name: &'static CStr = Box::leak(
+			CString::new(path.to_str().unwrap()).unwrap().into_boxed_c_str()
+		);
+		let symbols = [CString::new("reload_symbol").unwrap()];
+		let lib = ReloadableLibrary::new(name, symbols.clone(), None).unwrap();
+
+		// The file hasn't changed since it was loaded, so try_reload is a no-op.
+		assert!(!lib.try_reload().unwrap());
+
+		let symbol = unsafe { lib.get_symbol::<usize>(&symbols[0]) }.unwrap();
+		assert_eq!(*symbol.get_loaded(), 0x1111);
+
+		// Rewrite the file with a different symbol value and a different size, so
+		// the snapshot changes; try_reload should pick it up and swap in the new
+		// symbol.
+		write_fixture(&path, &["reload_symbol=0x2222", "padding_symbol=0x3"]);
+		assert!(lib.try_reload().unwrap());
+		assert_eq!(*symbol.get_loaded(), 0x2222);
+
+		// Deleting the backing file makes any further reload fail...
+		std::fs::remove_file(&path).unwrap();
+		assert!(lib.reload().is_err());
+
+		// ...but the symbol obtained before the failed reload is still valid, since
+		// a failed reload must not disturb the currently published library.
+		assert_eq!(*symbol.get_loaded(), 0x2222);
+	}
+}